@@ -1,6 +1,9 @@
 fn main() {
     let expression = r#"(2 + 3 * sin(π/4)) / (sqrt(9) + log(100, 10)) - 2^3"#;
-    let tokens = bitexpr::tokenizer::tokenize(expression).unwrap();
+    let (tokens, errors) = bitexpr::tokenizer::tokenize(expression);
 
     println!("{:?}", tokens);
+    for error in &errors {
+        eprintln!("{}", error);
+    }
 }
@@ -1,3 +1,5 @@
+use std::str::Chars;
+
 use thiserror::Error;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -9,6 +11,63 @@ pub enum Token {
     StringLiteral(String),
     Function(String),
     Comma,
+    Number(f64, NumberLiteral),
+    /// A character the scanner couldn't make sense of on its own (a lone
+    /// `!`, a stray `&`, a symbol outside the grammar). Emitted instead of
+    /// aborting so the rest of the input still gets tokenized.
+    Unknown(char),
+    Boolean(bool),
+    Null,
+    Keyword(Keyword),
+}
+
+/// Words the expression grammar reserves, centralized here so the parser
+/// can match on `Keyword` instead of string-comparing identifiers.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Keyword {
+    And,
+    Or,
+    Not,
+    In,
+    If,
+    Then,
+    Else,
+}
+
+impl Keyword {
+    fn from_str(s: &str) -> Option<Keyword> {
+        match s {
+            "and" => Some(Keyword::And),
+            "or" => Some(Keyword::Or),
+            "not" => Some(Keyword::Not),
+            "in" => Some(Keyword::In),
+            "if" => Some(Keyword::If),
+            "then" => Some(Keyword::Then),
+            "else" => Some(Keyword::Else),
+            _ => None,
+        }
+    }
+}
+
+/// Matches a scanned identifier against the reserved boolean/null literals
+/// and the [`Keyword`] table, so callers get a dedicated token instead of
+/// a plain `Identifier` for words the grammar treats specially.
+fn keyword_token(identifier: &str) -> Option<Token> {
+    match identifier {
+        "true" => Some(Token::Boolean(true)),
+        "false" => Some(Token::Boolean(false)),
+        "null" => Some(Token::Null),
+        _ => Keyword::from_str(identifier).map(Token::Keyword),
+    }
+}
+
+/// The original text and base of a scanned numeric literal, kept alongside
+/// the parsed `f64` so consumers can tell `0x2` from `2` or reformat the
+/// literal verbatim.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NumberLiteral {
+    pub text: String,
+    pub base: u32,
 }
 
 impl std::fmt::Display for Token {
@@ -21,6 +80,11 @@ impl std::fmt::Display for Token {
             Token::StringLiteral(s) => write!(f, "\"{}\"", s),
             Token::Function(s) => write!(f, "{}", s),
             Token::Comma => write!(f, ","),
+            Token::Number(value, literal) => write!(f, "{} ({})", value, literal.text),
+            Token::Unknown(c) => write!(f, "{}", c),
+            Token::Boolean(b) => write!(f, "{}", b),
+            Token::Null => write!(f, "null"),
+            Token::Keyword(keyword) => write!(f, "{:?}", keyword),
         }
     }
 }
@@ -43,166 +107,948 @@ pub enum Operator {
     Or,
 }
 
+/// A byte-offset range plus the line/column the span starts at, both
+/// 1-indexed. Lets callers (a parser, a REPL) point at the exact source
+/// location a token or error came from.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: u32,
+    pub col: u32,
+}
+
+/// A value paired with the span of source it was scanned from.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+}
+
 #[derive(Debug, PartialEq, Error)]
 pub enum TokenizerError {
-    #[error("Unexpected character '{0}'")]
-    UnexpectedChar(char),
+    #[error("Unexpected character '{found}'")]
+    UnexpectedChar { found: char, span: Span },
     #[error("Unexpected end of string after escape character")]
-    UnexpectedEndOfString,
+    UnexpectedEndOfString { span: Span },
+    #[error("Invalid number literal '{text}'")]
+    InvalidNumber { text: String, span: Span },
+    #[error("Unterminated block comment")]
+    UnterminatedComment { span: Span },
+    #[error("Invalid escape sequence '\\{found}'")]
+    InvalidEscape { found: String, span: Span },
+    #[error("Unterminated string literal")]
+    UnterminatedString { span: Span },
+}
+
+/// Sentinel returned by [`Cursor::first`]/[`Cursor::second`] once the input
+/// is exhausted, so lookahead never has to thread `Option<char>` through
+/// every predicate.
+const EOF_CHAR: char = '\0';
+
+/// A cursor over the source text that separates pure scanning (peek two
+/// chars ahead, bump, eat-while) from the token/error reporting built on
+/// top of it, tracking the running byte offset and 1-indexed line/column
+/// as it goes so every token and error can carry a [`Span`].
+#[derive(Clone)]
+struct Cursor<'a> {
+    chars: Chars<'a>,
+    offset: usize,
+    line: u32,
+    col: u32,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars(),
+            offset: 0,
+            line: 1,
+            col: 1,
+        }
+    }
+
+    fn first(&self) -> char {
+        self.chars.clone().next().unwrap_or(EOF_CHAR)
+    }
+
+    fn second(&self) -> char {
+        let mut iter = self.chars.clone();
+        iter.next();
+        iter.next().unwrap_or(EOF_CHAR)
+    }
+
+    fn is_eof(&self) -> bool {
+        self.chars.as_str().is_empty()
+    }
+
+    fn position(&self) -> (usize, u32, u32) {
+        (self.offset, self.line, self.col)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        self.offset += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
+        Some(c)
+    }
+
+    fn eat_while(&mut self, mut predicate: impl FnMut(char) -> bool) {
+        while predicate(self.first()) && !self.is_eof() {
+            self.bump();
+        }
+    }
+}
+
+fn span_from(start: (usize, u32, u32), end: (usize, u32, u32)) -> Span {
+    Span {
+        start: start.0,
+        end: end.0,
+        line: start.1,
+        col: start.2,
+    }
+}
+
+/// Returns whether `c` is a valid digit for `base` (2, 8, 10 or 16).
+fn is_in_base(c: char, base: u32) -> bool {
+    match base {
+        2 => matches!(c, '0'..='1'),
+        8 => matches!(c, '0'..='7'),
+        16 => c.is_ascii_hexdigit(),
+        _ => c.is_ascii_digit(),
+    }
 }
 
-// todo: rewrite using a state machine
-pub fn tokenize(expression: &str) -> Result<Vec<Token>, TokenizerError> {
+fn base_prefix(c: char) -> Option<u32> {
+    match c {
+        'b' | 'B' => Some(2),
+        'o' | 'O' => Some(8),
+        'x' | 'X' => Some(16),
+        _ => None,
+    }
+}
+
+/// Returns the base of the `0b`/`0o`/`0x` prefix at `cursor`'s position, if
+/// there is one.
+fn prefixed_base(cursor: &Cursor) -> Option<u32> {
+    if cursor.first() == '0' {
+        base_prefix(cursor.second())
+    } else {
+        None
+    }
+}
+
+/// Scans a numeric literal starting at the current position of `cursor`.
+///
+/// Handles base-prefixed integers (`0b`, `0o`, `0x`), underscore digit
+/// separators and decimal floats with exponents (`1.5e-3`). The caller is
+/// responsible for having peeked that the literal starts with an ASCII
+/// digit, or a `.` followed by one, and for passing the position `cursor`
+/// was at when the literal started.
+fn tokenize_number(
+    cursor: &mut Cursor,
+    input: &str,
+    start: (usize, u32, u32),
+) -> Result<Token, TokenizerError> {
+    if let Some(base) = prefixed_base(cursor) {
+        cursor.bump();
+        cursor.bump();
+
+        let digits_start = cursor.position().0;
+        cursor.eat_while(|c| is_in_base(c, base) || c == '_');
+        let digits_end = cursor.position().0;
+        let digits: String = input[digits_start..digits_end]
+            .chars()
+            .filter(|&c| c != '_')
+            .collect();
+
+        let end = cursor.position();
+        let text = input[start.0..end.0].to_string();
+
+        if digits.is_empty() {
+            return Err(TokenizerError::InvalidNumber {
+                text,
+                span: span_from(start, end),
+            });
+        }
+
+        let value =
+            u128::from_str_radix(&digits, base).map_err(|_| TokenizerError::InvalidNumber {
+                text: text.clone(),
+                span: span_from(start, end),
+            })? as f64;
+        return Ok(Token::Number(value, NumberLiteral { text, base }));
+    }
+
+    cursor.eat_while(|c| c.is_ascii_digit() || c == '_');
+
+    if cursor.first() == '.' && cursor.second().is_ascii_digit() {
+        cursor.bump();
+        cursor.eat_while(|c| c.is_ascii_digit() || c == '_');
+    }
+
+    if matches!(cursor.first(), 'e' | 'E') {
+        let mut lookahead = cursor.clone();
+        lookahead.bump();
+        if matches!(lookahead.first(), '+' | '-') {
+            lookahead.bump();
+        }
+        if lookahead.first().is_ascii_digit() {
+            cursor.bump();
+            if matches!(cursor.first(), '+' | '-') {
+                cursor.bump();
+            }
+            cursor.eat_while(|c| c.is_ascii_digit() || c == '_');
+        }
+    }
+
+    let end = cursor.position();
+    let text = input[start.0..end.0].to_string();
+    let cleaned: String = text.chars().filter(|&c| c != '_').collect();
+    let value = cleaned
+        .parse::<f64>()
+        .map_err(|_| TokenizerError::InvalidNumber {
+            text: text.clone(),
+            span: span_from(start, end),
+        })?;
+
+    Ok(Token::Number(value, NumberLiteral { text, base: 10 }))
+}
+
+/// Skips a `/* ... */` block comment, nestable so `/* /* */ */` closes
+/// correctly, assuming the opening `/*` has already been consumed from
+/// `cursor`.
+fn skip_block_comment(cursor: &mut Cursor, start: (usize, u32, u32)) -> Result<(), TokenizerError> {
+    let mut depth = 1u32;
+
+    while depth > 0 {
+        if cursor.is_eof() {
+            return Err(TokenizerError::UnterminatedComment {
+                span: span_from(start, cursor.position()),
+            });
+        }
+
+        match (cursor.first(), cursor.second()) {
+            ('/', '*') => {
+                cursor.bump();
+                cursor.bump();
+                depth += 1;
+            }
+            ('*', '/') => {
+                cursor.bump();
+                cursor.bump();
+                depth -= 1;
+            }
+            _ => {
+                cursor.bump();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Processes a single escape sequence, assuming the leading `\` has already
+/// been consumed from `cursor`. Recognizes `\n`, `\t`, `\r`, `\0`, `\\`,
+/// `\"`, `\xNN` (exactly two hex digits, a byte value) and `\u{...}` (one
+/// to six hex digits, validated as a Unicode scalar value). Anything else
+/// is a `TokenizerError::InvalidEscape` carrying the offending text.
+fn unescape(cursor: &mut Cursor, escape_start: (usize, u32, u32)) -> Result<char, TokenizerError> {
+    match cursor.bump() {
+        Some('n') => Ok('\n'),
+        Some('t') => Ok('\t'),
+        Some('r') => Ok('\r'),
+        Some('0') => Ok('\0'),
+        Some('\\') => Ok('\\'),
+        Some('"') => Ok('"'),
+        Some('x') => {
+            let mut hex = String::new();
+            while hex.len() < 2 && cursor.first().is_ascii_hexdigit() {
+                hex.push(cursor.first());
+                cursor.bump();
+            }
+
+            // `\x` is a fixed two-character escape body: if it wasn't all hex
+            // digits, consume whatever non-hex text took their place (up to
+            // the literal's closing quote) so it doesn't leak into the
+            // string as content and desync the scan.
+            while hex.len() < 2 && !matches!(cursor.first(), '"' | EOF_CHAR) {
+                hex.push(cursor.first());
+                cursor.bump();
+            }
+
+            if hex.len() != 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(TokenizerError::InvalidEscape {
+                    found: format!("x{}", hex),
+                    span: span_from(escape_start, cursor.position()),
+                });
+            }
+
+            Ok(u8::from_str_radix(&hex, 16).unwrap() as char)
+        }
+        Some('u') => {
+            if cursor.first() != '{' {
+                // No opening brace: consume any hex digits that look like a
+                // bare `\uXXXX` attempt (unsupported by this grammar) so
+                // they don't leak into the string as content.
+                let mut bad = String::new();
+                while bad.len() < 6 && cursor.first().is_ascii_hexdigit() {
+                    bad.push(cursor.first());
+                    cursor.bump();
+                }
+                return Err(TokenizerError::InvalidEscape {
+                    found: format!("u{}", bad),
+                    span: span_from(escape_start, cursor.position()),
+                });
+            }
+            cursor.bump();
+
+            let mut hex = String::new();
+            while hex.len() < 6 && cursor.first().is_ascii_hexdigit() {
+                hex.push(cursor.first());
+                cursor.bump();
+            }
+
+            // A malformed body (non-hex text, or too many digits) is
+            // resynced by consuming through the escape's closing `}`, so
+            // the leftover text doesn't leak into the string as content.
+            let mut malformed = false;
+            while !matches!(cursor.first(), '}' | '"' | EOF_CHAR) {
+                malformed = true;
+                cursor.bump();
+            }
+            let closed = cursor.first() == '}';
+            if closed {
+                cursor.bump();
+            }
+
+            if hex.is_empty() || malformed || !closed {
+                return Err(TokenizerError::InvalidEscape {
+                    found: format!("u{{{}", hex),
+                    span: span_from(escape_start, cursor.position()),
+                });
+            }
+
+            let code_point = u32::from_str_radix(&hex, 16).unwrap();
+            char::from_u32(code_point).ok_or_else(|| TokenizerError::InvalidEscape {
+                found: format!("u{{{}}}", hex),
+                span: span_from(escape_start, cursor.position()),
+            })
+        }
+        Some(found) => Err(TokenizerError::InvalidEscape {
+            found: found.to_string(),
+            span: span_from(escape_start, cursor.position()),
+        }),
+        None => Err(TokenizerError::UnexpectedEndOfString {
+            span: span_from(escape_start, cursor.position()),
+        }),
+    }
+}
+
+/// Scans a `"..."` string literal, assuming the opening quote has already
+/// been consumed from `cursor`.
+///
+/// A bad escape sequence is non-fatal: it's recorded onto `errors` and
+/// scanning continues to the literal's actual closing quote, rather than
+/// aborting and leaving the rest of the string to desync the scanner.
+/// Only running off the end of the input without finding that quote is
+/// fatal to the literal.
+fn tokenize_string(
+    cursor: &mut Cursor,
+    start: (usize, u32, u32),
+    errors: &mut Vec<TokenizerError>,
+) -> Result<Token, TokenizerError> {
+    let mut string_literal = String::new();
+
+    loop {
+        if cursor.is_eof() {
+            return Err(TokenizerError::UnterminatedString {
+                span: span_from(start, cursor.position()),
+            });
+        }
+
+        match cursor.first() {
+            '\\' => {
+                let escape_start = cursor.position();
+                cursor.bump();
+                match unescape(cursor, escape_start) {
+                    Ok(c) => string_literal.push(c),
+                    Err(err) => errors.push(err),
+                }
+            }
+            '"' => {
+                cursor.bump();
+                break;
+            }
+            c => {
+                string_literal.push(c);
+                cursor.bump();
+            }
+        }
+    }
+
+    Ok(Token::StringLiteral(string_literal))
+}
+
+/// Scans an identifier (or, if immediately followed by `(`, a function
+/// name), assuming the first character has not yet been consumed.
+fn tokenize_identifier(cursor: &mut Cursor) -> Result<Token, TokenizerError> {
+    let mut identifier = String::new();
+
+    loop {
+        match cursor.first() {
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {
+                identifier.push(c);
+                cursor.bump();
+            }
+            '\\' => {
+                let escape_start = cursor.position();
+                cursor.bump();
+                match cursor.bump() {
+                    Some(escaped_char) => identifier.push(escaped_char),
+                    None => {
+                        return Err(TokenizerError::UnexpectedEndOfString {
+                            span: span_from(escape_start, cursor.position()),
+                        });
+                    }
+                }
+            }
+            _ => break,
+        }
+    }
+
+    if let Some(token) = keyword_token(&identifier) {
+        return Ok(token);
+    }
+
+    if cursor.first() == '(' {
+        Ok(Token::Function(identifier))
+    } else {
+        Ok(Token::Identifier(identifier))
+    }
+}
+
+/// Tokenizes `expression`, always producing a full token stream: lexing
+/// never aborts on a bad character. A character the scanner can't make
+/// sense of becomes a [`Token::Unknown`]; a malformed number, string or
+/// block comment produces no token. Every [`TokenizerError`] is appended
+/// to the returned error list, so callers (an editor, a REPL) can surface
+/// every problem in the input at once instead of stopping at the first one.
+pub fn tokenize_spanned(expression: &str) -> (Vec<Spanned<Token>>, Vec<TokenizerError>) {
     let mut tokens = Vec::new();
-    let mut chars = expression.chars().peekable();
+    let mut errors = Vec::new();
+    let mut cursor = Cursor::new(expression);
+
+    while !cursor.is_eof() {
+        let start = cursor.position();
+        let ch = cursor.first();
 
-    while let Some(&ch) = chars.peek() {
-        match ch {
-            ' ' => {
-                // Ignore spaces
-                chars.next();
+        let result: Result<Option<Token>, TokenizerError> = match ch {
+            ' ' | '\t' | '\n' | '\r' => {
+                cursor.bump();
+                Ok(None)
             }
             '(' => {
-                tokens.push(Token::OpenParenthesis);
-                chars.next();
+                cursor.bump();
+                Ok(Some(Token::OpenParenthesis))
             }
             ')' => {
-                tokens.push(Token::CloseParenthesis);
-                chars.next();
+                cursor.bump();
+                Ok(Some(Token::CloseParenthesis))
             }
             ',' => {
-                tokens.push(Token::Comma);
-                chars.next();
+                cursor.bump();
+                Ok(Some(Token::Comma))
             }
             '+' => {
-                tokens.push(Token::Operator(Operator::Plus));
-                chars.next();
+                cursor.bump();
+                Ok(Some(Token::Operator(Operator::Plus)))
             }
             '-' => {
-                tokens.push(Token::Operator(Operator::Minus));
-                chars.next();
+                cursor.bump();
+                Ok(Some(Token::Operator(Operator::Minus)))
             }
             '*' => {
-                tokens.push(Token::Operator(Operator::Multiply));
-                chars.next();
+                cursor.bump();
+                Ok(Some(Token::Operator(Operator::Multiply)))
+            }
+            '/' if cursor.second() == '*' => {
+                cursor.bump();
+                cursor.bump();
+                skip_block_comment(&mut cursor, start).map(|()| None)
             }
             '/' => {
-                tokens.push(Token::Operator(Operator::Divide));
-                chars.next();
+                cursor.bump();
+                Ok(Some(Token::Operator(Operator::Divide)))
+            }
+            '#' => {
+                cursor.bump();
+                cursor.eat_while(|c| c != '\n');
+                Ok(None)
             }
             '%' => {
-                tokens.push(Token::Operator(Operator::Modulo));
-                chars.next();
+                cursor.bump();
+                Ok(Some(Token::Operator(Operator::Modulo)))
             }
             '^' => {
-                tokens.push(Token::Operator(Operator::Power));
-                chars.next();
+                cursor.bump();
+                Ok(Some(Token::Operator(Operator::Power)))
             }
             '=' => {
-                chars.next();
-                if let Some(&'=') = chars.peek() {
-                    tokens.push(Token::Operator(Operator::Equal));
-                    chars.next();
+                cursor.bump();
+                if cursor.first() == '=' {
+                    cursor.bump();
+                    Ok(Some(Token::Operator(Operator::Equal)))
                 } else {
-                    return Err(TokenizerError::UnexpectedChar('='));
+                    Err(TokenizerError::UnexpectedChar {
+                        found: '=',
+                        span: span_from(start, cursor.position()),
+                    })
                 }
             }
             '!' => {
-                chars.next();
-                if let Some(&'=') = chars.peek() {
-                    tokens.push(Token::Operator(Operator::NotEqual));
-                    chars.next();
+                cursor.bump();
+                if cursor.first() == '=' {
+                    cursor.bump();
+                    Ok(Some(Token::Operator(Operator::NotEqual)))
                 } else {
-                    return Err(TokenizerError::UnexpectedChar('!'));
+                    Err(TokenizerError::UnexpectedChar {
+                        found: '!',
+                        span: span_from(start, cursor.position()),
+                    })
                 }
             }
             '<' => {
-                chars.next();
-                if let Some(&'=') = chars.peek() {
-                    tokens.push(Token::Operator(Operator::LessEqual));
-                    chars.next();
+                cursor.bump();
+                if cursor.first() == '=' {
+                    cursor.bump();
+                    Ok(Some(Token::Operator(Operator::LessEqual)))
                 } else {
-                    tokens.push(Token::Operator(Operator::Less));
+                    Ok(Some(Token::Operator(Operator::Less)))
                 }
             }
             '>' => {
-                chars.next();
-                if let Some(&'=') = chars.peek() {
-                    tokens.push(Token::Operator(Operator::GreaterEqual));
-                    chars.next();
+                cursor.bump();
+                if cursor.first() == '=' {
+                    cursor.bump();
+                    Ok(Some(Token::Operator(Operator::GreaterEqual)))
                 } else {
-                    tokens.push(Token::Operator(Operator::Greater));
+                    Ok(Some(Token::Operator(Operator::Greater)))
                 }
             }
             '&' => {
-                chars.next();
-                if let Some(&'&') = chars.peek() {
-                    tokens.push(Token::Operator(Operator::And));
-                    chars.next();
+                cursor.bump();
+                if cursor.first() == '&' {
+                    cursor.bump();
+                    Ok(Some(Token::Operator(Operator::And)))
                 } else {
-                    return Err(TokenizerError::UnexpectedChar('&'));
+                    Err(TokenizerError::UnexpectedChar {
+                        found: '&',
+                        span: span_from(start, cursor.position()),
+                    })
                 }
             }
             '|' => {
-                chars.next();
-                if let Some(&'|') = chars.peek() {
-                    tokens.push(Token::Operator(Operator::Or));
-                    chars.next();
+                cursor.bump();
+                if cursor.first() == '|' {
+                    cursor.bump();
+                    Ok(Some(Token::Operator(Operator::Or)))
                 } else {
-                    return Err(TokenizerError::UnexpectedChar('|'));
+                    Err(TokenizerError::UnexpectedChar {
+                        found: '|',
+                        span: span_from(start, cursor.position()),
+                    })
                 }
             }
             '"' => {
-                chars.next();
-                let mut string_literal = String::new();
-                while let Some(&ch) = chars.peek() {
-                    if ch == '\\' {
-                        chars.next();
-                        if let Some(escaped_char) = chars.next() {
-                            match escaped_char {
-                                'n' => string_literal.push('\n'),
-                                't' => string_literal.push('\t'),
-                                '"' => string_literal.push('"'),
-                                _ => string_literal.push(escaped_char),
-                            }
-                        } else {
-                            return Err(TokenizerError::UnexpectedEndOfString);
-                        }
-                    } else if ch == '"' {
-                        chars.next();
-                        break;
-                    } else {
-                        string_literal.push(ch);
-                        chars.next();
-                    }
-                }
-                tokens.push(Token::StringLiteral(string_literal));
+                cursor.bump();
+                tokenize_string(&mut cursor, start, &mut errors).map(Some)
             }
-            _ => {
-                let mut identifier = String::new();
-                while let Some(&ch) = chars.peek() {
-                    if ch.is_alphanumeric() || ch == '_' || ch == '.' {
-                        identifier.push(ch);
-                        chars.next();
-                    } else if ch == '\\' {
-                        chars.next();
-                        if let Some(escaped_char) = chars.next() {
-                            identifier.push(escaped_char);
-                        } else {
-                            return Err(TokenizerError::UnexpectedEndOfString);
-                        }
-                    } else {
-                        break;
+            c if c.is_ascii_digit() || (c == '.' && cursor.second().is_ascii_digit()) => {
+                tokenize_number(&mut cursor, expression, start).map(Some)
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {
+                tokenize_identifier(&mut cursor).map(Some)
+            }
+            unknown => {
+                cursor.bump();
+                Err(TokenizerError::UnexpectedChar {
+                    found: unknown,
+                    span: span_from(start, cursor.position()),
+                })
+            }
+        };
+
+        let token = match result {
+            Ok(token) => token,
+            Err(err) => {
+                let unknown_token = match &err {
+                    TokenizerError::UnexpectedChar { found, .. } => Some(Token::Unknown(*found)),
+                    TokenizerError::UnexpectedEndOfString { .. }
+                    | TokenizerError::InvalidNumber { .. }
+                    | TokenizerError::UnterminatedComment { .. }
+                    | TokenizerError::InvalidEscape { .. }
+                    | TokenizerError::UnterminatedString { .. } => None,
+                };
+                errors.push(err);
+                unknown_token
+            }
+        };
+
+        if let Some(token) = token {
+            let end = cursor.position();
+            tokens.push(Spanned {
+                value: token,
+                span: span_from(start, end),
+            });
+        }
+    }
+
+    (tokens, errors)
+}
+
+/// Convenience wrapper over [`tokenize_spanned`] for callers that don't
+/// need source locations.
+pub fn tokenize(expression: &str) -> (Vec<Token>, Vec<TokenizerError>) {
+    let (tokens, errors) = tokenize_spanned(expression);
+    (
+        tokens.into_iter().map(|spanned| spanned.value).collect(),
+        errors,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_comment_is_skipped_to_end_of_line() {
+        let (tokens, errors) = tokenize("1 # a comment\n2");
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Number(
+                    1.0,
+                    NumberLiteral {
+                        text: "1".to_string(),
+                        base: 10
+                    }
+                ),
+                Token::Number(
+                    2.0,
+                    NumberLiteral {
+                        text: "2".to_string(),
+                        base: 10
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_spanned_reports_the_span_of_each_token() {
+        let (tokens, errors) = tokenize_spanned("  12");
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens,
+            vec![Spanned {
+                value: Token::Number(
+                    12.0,
+                    NumberLiteral {
+                        text: "12".to_string(),
+                        base: 10
                     }
+                ),
+                span: Span {
+                    start: 2,
+                    end: 4,
+                    line: 1,
+                    col: 3
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn each_bad_character_is_its_own_unknown_token_and_error() {
+        let (tokens, errors) = tokenize("! & @");
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Unknown('!'),
+                Token::Unknown('&'),
+                Token::Unknown('@'),
+            ]
+        );
+        assert_eq!(
+            errors,
+            vec![
+                TokenizerError::UnexpectedChar {
+                    found: '!',
+                    span: Span {
+                        start: 0,
+                        end: 1,
+                        line: 1,
+                        col: 1
+                    },
+                },
+                TokenizerError::UnexpectedChar {
+                    found: '&',
+                    span: Span {
+                        start: 2,
+                        end: 3,
+                        line: 1,
+                        col: 3
+                    },
+                },
+                TokenizerError::UnexpectedChar {
+                    found: '@',
+                    span: Span {
+                        start: 4,
+                        end: 5,
+                        line: 1,
+                        col: 5
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn decimal_number_with_exponent() {
+        let (tokens, errors) = tokenize("1.5e-3");
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens,
+            vec![Token::Number(
+                1.5e-3,
+                NumberLiteral {
+                    text: "1.5e-3".to_string(),
+                    base: 10
                 }
-                if let Some(&'(') = chars.peek() {
-                    tokens.push(Token::Function(identifier));
-                } else {
-                    tokens.push(Token::Identifier(identifier));
+            )]
+        );
+    }
+
+    #[test]
+    fn binary_octal_and_hex_literals() {
+        let (tokens, errors) = tokenize("0b101 0o17 0xFF");
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Number(
+                    5.0,
+                    NumberLiteral {
+                        text: "0b101".to_string(),
+                        base: 2
+                    }
+                ),
+                Token::Number(
+                    15.0,
+                    NumberLiteral {
+                        text: "0o17".to_string(),
+                        base: 8
+                    }
+                ),
+                Token::Number(
+                    255.0,
+                    NumberLiteral {
+                        text: "0xFF".to_string(),
+                        base: 16
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn hex_prefix_with_no_digits_is_an_error() {
+        let (tokens, errors) = tokenize("0x");
+        assert!(tokens.is_empty());
+        assert_eq!(
+            errors,
+            vec![TokenizerError::InvalidNumber {
+                text: "0x".to_string(),
+                span: Span {
+                    start: 0,
+                    end: 2,
+                    line: 1,
+                    col: 1
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn nested_block_comment_is_fully_skipped() {
+        let (tokens, errors) = tokenize("1 /* outer /* inner */ still outer */ 2");
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Number(
+                    1.0,
+                    NumberLiteral {
+                        text: "1".to_string(),
+                        base: 10
+                    }
+                ),
+                Token::Number(
+                    2.0,
+                    NumberLiteral {
+                        text: "2".to_string(),
+                        base: 10
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_block_comment_is_an_error() {
+        let (tokens, errors) = tokenize("1 /* never closes");
+        assert_eq!(
+            tokens,
+            vec![Token::Number(
+                1.0,
+                NumberLiteral {
+                    text: "1".to_string(),
+                    base: 10
                 }
-            }
-        }
+            )]
+        );
+        assert!(matches!(
+            errors.as_slice(),
+            [TokenizerError::UnterminatedComment { .. }]
+        ));
+    }
+
+    #[test]
+    fn string_escapes_happy_path() {
+        let (tokens, errors) = tokenize(r#""\x41\u{1F600}""#);
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens,
+            vec![Token::StringLiteral(format!("A{}", '\u{1F600}'))]
+        );
     }
 
-    Ok(tokens)
+    #[test]
+    fn unicode_escape_rejects_surrogates_and_out_of_range() {
+        let (tokens, errors) = tokenize(r#""\u{D800}""#);
+        assert_eq!(tokens, vec![Token::StringLiteral(String::new())]);
+        assert!(matches!(
+            errors.as_slice(),
+            [TokenizerError::InvalidEscape { .. }]
+        ));
+
+        let (tokens, errors) = tokenize(r#""\u{110000}""#);
+        assert_eq!(tokens, vec![Token::StringLiteral(String::new())]);
+        assert!(matches!(
+            errors.as_slice(),
+            [TokenizerError::InvalidEscape { .. }]
+        ));
+    }
+
+    #[test]
+    fn bad_escape_does_not_desync_the_rest_of_the_scan() {
+        let (tokens, errors) = tokenize(r#""\q" + 1"#);
+        assert_eq!(
+            tokens,
+            vec![
+                Token::StringLiteral(String::new()),
+                Token::Operator(Operator::Plus),
+                Token::Number(
+                    1.0,
+                    NumberLiteral {
+                        text: "1".to_string(),
+                        base: 10
+                    }
+                ),
+            ]
+        );
+        assert!(matches!(
+            errors.as_slice(),
+            [TokenizerError::InvalidEscape { .. }]
+        ));
+    }
+
+    #[test]
+    fn malformed_hex_and_unicode_escapes_do_not_leak_into_the_literal() {
+        let (tokens, errors) = tokenize(r#""\xZZ""#);
+        assert_eq!(tokens, vec![Token::StringLiteral(String::new())]);
+        assert!(matches!(
+            errors.as_slice(),
+            [TokenizerError::InvalidEscape { .. }]
+        ));
+
+        let (tokens, errors) = tokenize(r#""\u41" + 1"#);
+        assert_eq!(
+            tokens,
+            vec![
+                Token::StringLiteral(String::new()),
+                Token::Operator(Operator::Plus),
+                Token::Number(
+                    1.0,
+                    NumberLiteral {
+                        text: "1".to_string(),
+                        base: 10
+                    }
+                ),
+            ]
+        );
+        assert!(matches!(
+            errors.as_slice(),
+            [TokenizerError::InvalidEscape { .. }]
+        ));
+
+        let (tokens, errors) = tokenize(r#""\u{ZZ}" + 1"#);
+        assert_eq!(
+            tokens,
+            vec![
+                Token::StringLiteral(String::new()),
+                Token::Operator(Operator::Plus),
+                Token::Number(
+                    1.0,
+                    NumberLiteral {
+                        text: "1".to_string(),
+                        base: 10
+                    }
+                ),
+            ]
+        );
+        assert!(matches!(
+            errors.as_slice(),
+            [TokenizerError::InvalidEscape { .. }]
+        ));
+    }
+
+    #[test]
+    fn keywords_booleans_and_null_are_distinct_from_identifiers() {
+        let (tokens, errors) = tokenize("true false null and or not in if then else x");
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Boolean(true),
+                Token::Boolean(false),
+                Token::Null,
+                Token::Keyword(Keyword::And),
+                Token::Keyword(Keyword::Or),
+                Token::Keyword(Keyword::Not),
+                Token::Keyword(Keyword::In),
+                Token::Keyword(Keyword::If),
+                Token::Keyword(Keyword::Then),
+                Token::Keyword(Keyword::Else),
+                Token::Identifier("x".to_string()),
+            ]
+        );
+    }
 }